@@ -10,8 +10,11 @@ use std::{
 use clap::Parser;
 use humansize::{FormatSize, DECIMAL};
 use profile::{Profile, ProfileError};
+use rayon::prelude::*;
+use trash::TrashError;
 
 mod profile;
+mod trash;
 
 /// Performs cleaning on directories using profiles.
 #[derive(Debug, Parser)]
@@ -20,12 +23,20 @@ struct Args {
 	/// Specifies the profile file
 	#[arg(short, long)]
 	profile: PathBuf,
+
+	/// Moves matched paths to the XDG trash instead of deleting them permanently
+	#[arg(long)]
+	trash: bool,
+
+	/// Reports what would be deleted without touching the filesystem
+	#[arg(long)]
+	dry_run: bool,
 }
 
 fn main() {
 	let args = Args::parse();
 
-	match clean(args.profile) {
+	match clean(args.profile, args.trash, args.dry_run) {
 		Ok(()) => println!("Successfully cleaned items."),
 		Err(e) => println!("Failed to clean items: {}.", e),
 	}
@@ -36,9 +47,19 @@ fn main() {
 enum CleanError {
 	/// Indicates that the profile could not be loaded.
 	FailedToLoad(ProfileError),
+}
 
-	/// Indicates that the entry could not be removed.
-	FailedToRemove(RemoveError),
+/// Represents the outcome of attempting to remove a single expanded path.
+#[derive(Debug)]
+enum PathOutcome {
+	/// Indicates that the path was removed, carrying the number of bytes reclaimed.
+	Removed(PathBuf, u64),
+
+	/// Indicates that the path was skipped, e.g. because it could not be canonicalised.
+	Skipped(PathBuf),
+
+	/// Indicates that the path could not be removed.
+	Failed(PathBuf, RemoveError),
 }
 
 /// Represents a remove-related error.
@@ -55,6 +76,9 @@ enum RemoveError {
 
 	/// Indicates that a particular directory could not be read for its files.
 	FailedToReadDirectory(io::Error),
+
+	/// Indicates that a particular path could not be moved to the trash.
+	FailedToTrash(TrashError),
 }
 
 /// Indicates the result of a clean operation.
@@ -67,7 +91,6 @@ impl Display for CleanError {
 	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
 		match self {
 			Self::FailedToLoad(e) => write!(f, "failed to load profile [{}]", e),
-			Self::FailedToRemove(e) => write!(f, "failed to remove path [{}]", e),
 		}
 	}
 }
@@ -79,6 +102,7 @@ impl Display for RemoveError {
 			Self::FailedToRemoveFile(e) => write!(f, "failed to remove file [{}]", e),
 			Self::FailedToRemoveDirectory(e) => write!(f, "failed to remove directory [{}]", e),
 			Self::FailedToReadDirectory(e) => write!(f, "failed to read directory files [{}]", e),
+			Self::FailedToTrash(e) => write!(f, "failed to move path to trash [{}]", e),
 		}
 	}
 }
@@ -87,7 +111,7 @@ impl Error for CleanError {}
 impl Error for RemoveError {}
 
 /// Cleans the entries described by the specified profile in the specified mode.
-fn clean<T>(profile: T) -> CleanResult
+fn clean<T>(profile: T, trash: bool, dry_run: bool) -> CleanResult
 where
 	T: AsRef<Path>,
 {
@@ -99,55 +123,97 @@ where
 
 	let start = Instant::now();
 
-	// Expand each entry to all of its paths.
+	// Expand each entry to all of its paths, in parallel.
 
 	#[rustfmt::skip]
-	let paths: Vec<PathBuf> = profile.entries.into_iter()
-		.flat_map(|e| e.expand()).flatten()
-		.flat_map(|p| p.canonicalize())
+	let paths: Vec<PathBuf> = profile.entries.into_par_iter()
+		.flat_map_iter(|e| e.expand().into_iter().flatten())
 		.collect();
 
 	println!("Expanded {} paths in {:#?}.", paths.len(), start.elapsed());
-	println!("Deleting {} paths...", paths.len());
+	println!("{} {} paths...", if dry_run { "Previewing" } else { "Deleting" }, paths.len());
 
 	let start = Instant::now();
 
-	// Iterate through each path and remove it.
+	// Canonicalise and remove each path in parallel, recording an explicit outcome for every one.
 
-	let mut total = 0usize;
-	let mut size = 0u64;
+	let outcomes: Vec<PathOutcome> = paths
+		.into_par_iter()
+		.map(|path| match path.canonicalize() {
+			Ok(path) => {
+				let prefix = if dry_run { "[dry-run] Would delete" } else { "Deleting" };
 
-	for (index, path) in paths.iter().enumerate() {
-		println!("Deleting path {} of {}: <{}>...", index + 1, paths.len(), path.display());
+				println!("{} path <{}>...", prefix, path.display());
 
-		match remove(path).map_err(CleanError::FailedToRemove) {
-			Ok(s) => {
-				total += 1;
-				size += s;
-			}
-			Err(e) => {
-				println!("Failed to delete path: {}.", e);
+				match remove(&path, trash, dry_run) {
+					Ok(size) => PathOutcome::Removed(path, size),
+					Err(e) => PathOutcome::Failed(path, e),
+				}
 			}
+			Err(_) => PathOutcome::Skipped(path),
+		})
+		.collect();
+
+	// Reduce the outcomes into a summary report.
+
+	let (total, size, skipped, failures) = outcomes.into_iter().fold((0usize, 0u64, Vec::new(), Vec::new()), |(total, size, mut skipped, mut failures), outcome| match outcome {
+		PathOutcome::Removed(_, s) => (total + 1, size + s, skipped, failures),
+		PathOutcome::Skipped(path) => {
+			skipped.push(path);
+
+			(total, size, skipped, failures)
+		}
+		PathOutcome::Failed(path, e) => {
+			failures.push((path, e));
+
+			(total, size, skipped, failures)
+		}
+	});
+
+	let verb = if dry_run { "Would delete" } else { "Deleted" };
+
+	println!("{} {} paths in {:#?}, reclaiming {} of space.", verb, total, start.elapsed(), size.format_size(DECIMAL));
+
+	if !skipped.is_empty() {
+		println!("Skipped {} paths:", skipped.len());
+
+		for path in &skipped {
+			println!("  <{}>.", path.display());
 		}
 	}
 
-	println!("Deleted {} paths in {:#?}, reclaiming {} of space.", total, start.elapsed(), size.format_size(DECIMAL));
+	if !failures.is_empty() {
+		println!("Failed to {} {} paths:", if dry_run { "preview" } else { "delete" }, failures.len());
+
+		for (path, e) in &failures {
+			println!("  <{}>: {}.", path.display(), e);
+		}
+	}
 
 	Ok(())
 }
 
-/// Attempts to remove the specified path.
-fn remove<T>(path: T) -> RemoveResult
+/// Attempts to remove the specified path, optionally relocating it to the trash or simulating the removal instead of
+/// deleting it permanently.
+fn remove<T>(path: T, trash: bool, dry_run: bool) -> RemoveResult
 where
 	T: AsRef<Path>,
 {
+	if dry_run {
+		return self::trash::size_of(path.as_ref()).map_err(RemoveError::FailedToInspectPath);
+	}
+
+	if trash {
+		return self::trash::trash(&path).map_err(RemoveError::FailedToTrash);
+	}
+
 	let metadata = path.as_ref().metadata().map_err(RemoveError::FailedToInspectPath)?;
 
 	match &metadata {
 		m if m.is_dir() => {
 			#[rustfmt::skip]
 			let size = fs::read_dir(&path).map_err(RemoveError::FailedToReadDirectory)?
-				.flatten().map(|e| remove(e.path()))
+				.flatten().map(|e| remove(e.path(), false, false))
 				.flatten().sum();
 
 			fs::remove_dir(path).map_err(RemoveError::FailedToRemoveDirectory)?;
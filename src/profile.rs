@@ -1,11 +1,18 @@
 use std::{
+	collections::HashMap,
+	env,
 	error::Error,
 	fmt::{self, Display},
-	fs, io,
+	fs,
+	hash::Hasher,
+	io::{self, Read},
 	path::{Path, PathBuf},
+	time::{Duration, SystemTime},
 };
 
-use serde::Deserialize;
+use regex::Regex;
+use serde::{Deserialize, Deserializer};
+use siphasher::sip128::{Hasher128, SipHasher13};
 
 /// Represents a profile.
 #[derive(Debug, Deserialize)]
@@ -32,20 +39,98 @@ pub enum Entry {
 		/// The pattern to match.
 		pattern: String,
 
+		/// The syntax to interpret the pattern with.
+		#[serde(default)]
+		syntax: PatternSyntax,
+
 		/// The retention to use, if any.
 		retention: Option<Retention>,
 	},
 }
 
+/// Represents the syntax a pattern is interpreted with.
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PatternSyntax {
+	/// Indicates the pattern is a shell glob.
+	#[default]
+	Glob,
+
+	/// Indicates the pattern is a regular expression tested against each absolute path below the current directory.
+	Regexp,
+
+	/// Indicates the pattern is an exact absolute path prefix.
+	Literal,
+}
+
 /// Represents a retention.
 #[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum Retention {
+	/// Retains the newest matches per the chosen order, removing the rest.
+	Count {
+		/// The order to use for sorting matches (file names ascending, timestamps newest first).
+		order: Order,
+
+		/// The number of matches to retain.
+		count: usize,
+	},
+
+	/// Retains a single representative of each set of byte-identical matches, removing the duplicates.
+	Dedupe {
+		/// The order to use for choosing which duplicate to retain; the first in that order is kept.
+		order: Order,
+	},
+
+	/// Retains every match younger than the maximum age, removing the rest.
+	Age {
+		/// The timestamp to compare against the maximum age.
+		order: AgeOrder,
+
+		/// The maximum age a match may have before it is removed, e.g. `"30d"`.
+		#[serde(deserialize_with = "deserialize_duration")]
+		max_age: Duration,
+	},
+}
+
+/// Represents the timestamp used for age-based retention.
+#[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
-pub struct Retention {
-	/// The (ascending) order to use for sorting matches.
-	pub order: Order,
+pub enum AgeOrder {
+	/// Indicates to use the first created timestamp.
+	Created,
+
+	/// Indicates to use the last modified timestamp.
+	Modified,
+}
+
+/// Deserialises a duration from a string with a suffix of `d`, `h`, `m` or `s`, e.g. `"30d"`.
+fn deserialize_duration<'de, D>(deserializer: D) -> Result<Duration, D::Error>
+where
+	D: Deserializer<'de>,
+{
+	let value = String::deserialize(deserializer)?;
+
+	parse_duration(&value).map_err(serde::de::Error::custom)
+}
 
-	/// The number of matches to retain.
-	pub count: usize,
+/// Parses a duration from a string with a suffix of `d`, `h`, `m` or `s`, e.g. `"30d"`.
+fn parse_duration(value: &str) -> Result<Duration, String> {
+	let value = value.trim();
+	let suffix = value.chars().last().ok_or_else(|| format!("invalid duration '{}'", value))?;
+	let amount = &value[..value.len() - suffix.len_utf8()];
+
+	let amount: u64 = amount.parse().map_err(|_| format!("invalid duration '{}'", value))?;
+
+	let seconds = match suffix {
+		's' => amount,
+		'm' => amount * 60,
+		'h' => amount * 60 * 60,
+		'd' => amount * 60 * 60 * 24,
+		_ => return Err(format!("invalid duration suffix '{}'", suffix)),
+	};
+
+	Ok(Duration::from_secs(seconds))
 }
 
 /// Represents an order.
@@ -77,6 +162,12 @@ pub enum ProfileError {
 pub enum EntryError {
 	/// Indicates that the glob representing a pattern could not be parsed.
 	FailedToParse(glob::PatternError),
+
+	/// Indicates that the regex representing a pattern could not be compiled.
+	FailedToCompileRegex(regex::Error),
+
+	/// Indicates that the search root for a regex or literal pattern could not be resolved.
+	FailedToResolveRoot(io::Error),
 }
 
 pub type ProfileResult = Result<Profile, ProfileError>;
@@ -105,31 +196,184 @@ impl Entry {
 			} => Ok(vec![path]),
 			Self::Pattern {
 				pattern,
+				syntax,
 				retention,
 			} => {
-				// Expand the initial set of paths from the pattern.
+				// Expand the initial set of paths from the pattern, per its syntax.
+
+				let mut paths: Vec<PathBuf> = match syntax {
+					PatternSyntax::Glob => match glob::glob(&pattern) {
+						Ok(p) => p.flatten().collect(),
+						Err(e) => return Err(EntryError::FailedToParse(e)),
+					},
+					PatternSyntax::Regexp => {
+						let regex = Regex::new(&pattern).map_err(EntryError::FailedToCompileRegex)?;
+						let root = env::current_dir().map_err(EntryError::FailedToResolveRoot)?;
 
-				let mut paths: Vec<PathBuf> = match glob::glob(&pattern) {
-					Ok(p) => p.flatten().collect(),
-					Err(e) => return Err(EntryError::FailedToParse(e)),
+						walk(&root).into_iter().filter(|p| regex.is_match(&p.to_string_lossy())).collect()
+					}
+					PatternSyntax::Literal => {
+						let root = env::current_dir().map_err(EntryError::FailedToResolveRoot)?;
+
+						walk(&root).into_iter().filter(|p| p.to_string_lossy().starts_with(pattern.as_str())).collect()
+					}
 				};
 
-				// Sort and omit the paths that should be retained, if any.
+				// Apply the retention strategy, if any, to determine the paths to remove.
+
+				match retention {
+					Some(Retention::Count {
+						order,
+						count,
+					}) => {
+						sort_by_order(&mut paths, &order);
+						paths.drain(0..count.min(paths.len()));
+
+						Ok(paths)
+					}
+					Some(Retention::Dedupe {
+						order,
+					}) => Ok(dedupe(paths, &order)),
+					Some(Retention::Age {
+						order,
+						max_age,
+					}) => Ok(expire(paths, &order, max_age)),
+					None => Ok(paths),
+				}
+			}
+		}
+	}
+}
+
+/// Recursively walks the directory tree rooted at the specified path, returning every file and directory beneath it.
+/// Symlinks are never followed, so a self-referential symlink (e.g. `ln -s . loop`) cannot recurse without bound.
+fn walk(root: &Path) -> Vec<PathBuf> {
+	let mut paths = Vec::new();
+
+	if let Ok(entries) = fs::read_dir(root) {
+		for entry in entries.flatten() {
+			let path = entry.path();
+
+			if fs::symlink_metadata(&path).is_ok_and(|m| m.is_dir()) {
+				paths.extend(walk(&path));
+			}
+
+			paths.push(path);
+		}
+	}
+
+	paths
+}
+
+/// Sorts the specified paths by the specified order: file names ascending, timestamps newest first.
+fn sort_by_order(paths: &mut [PathBuf], order: &Order) {
+	paths.sort_by(|a, b| match order {
+		Order::FileName => a.file_name().cmp(&b.file_name()),
+		Order::Created => b.metadata().and_then(|m| m.created()).ok().cmp(&a.metadata().and_then(|m| m.created()).ok()),
+		Order::Modified => b.metadata().and_then(|m| m.modified()).ok().cmp(&a.metadata().and_then(|m| m.modified()).ok()),
+	});
+}
+
+/// Finds byte-identical duplicates among the specified paths, and returns every path but one representative per group.
+/// Candidates are narrowed in three stages to avoid hashing everything: by file length, then by a partial hash of the
+/// first block, then by a full hash of the entire contents.
+fn dedupe(paths: Vec<PathBuf>, order: &Order) -> Vec<PathBuf> {
+	let mut by_size: HashMap<u64, Vec<PathBuf>> = HashMap::new();
+
+	for path in paths {
+		if let Ok(metadata) = path.metadata() {
+			by_size.entry(metadata.len()).or_default().push(path);
+		}
+	}
+
+	let mut duplicates = Vec::new();
+
+	for (_, candidates) in by_size {
+		if candidates.len() < 2 {
+			continue;
+		}
+
+		let mut by_partial: HashMap<u128, Vec<PathBuf>> = HashMap::new();
+
+		for path in candidates {
+			if let Ok(hash) = partial_hash(&path) {
+				by_partial.entry(hash).or_default().push(path);
+			}
+		}
+
+		for (_, candidates) in by_partial {
+			if candidates.len() < 2 {
+				continue;
+			}
+
+			let mut by_full: HashMap<u128, Vec<PathBuf>> = HashMap::new();
 
-				if let Some(retention) = retention {
-					paths.sort_by(|a, b| match &retention.order {
-						Order::FileName => a.file_name().cmp(&b.file_name()),
-						Order::Created => b.metadata().and_then(|m| m.created()).ok().cmp(&a.metadata().and_then(|m| m.created()).ok()),
-						Order::Modified => b.metadata().and_then(|m| m.modified()).ok().cmp(&a.metadata().and_then(|m| m.modified()).ok()),
-					});
+			for path in candidates {
+				if let Ok(hash) = full_hash(&path) {
+					by_full.entry(hash).or_default().push(path);
+				}
+			}
 
-					paths.drain(0..retention.count);
+			for (_, mut group) in by_full {
+				if group.len() < 2 {
+					continue;
 				}
 
-				Ok(paths)
+				sort_by_order(&mut group, order);
+				duplicates.extend(group.drain(1..));
 			}
 		}
 	}
+
+	duplicates
+}
+
+/// Returns the paths older than the maximum age, per the `Created` or `Modified` timestamp described by the order.
+fn expire(paths: Vec<PathBuf>, order: &AgeOrder, max_age: Duration) -> Vec<PathBuf> {
+	let threshold = SystemTime::now().checked_sub(max_age);
+
+	paths
+		.into_iter()
+		.filter(|path| {
+			let timestamp = match order {
+				AgeOrder::Created => path.metadata().and_then(|m| m.created()),
+				AgeOrder::Modified => path.metadata().and_then(|m| m.modified()),
+			};
+
+			matches!((timestamp.ok(), threshold), (Some(timestamp), Some(threshold)) if timestamp < threshold)
+		})
+		.collect()
+}
+
+/// Hashes only the first 4096-byte block of the specified file, for cheaply narrowing duplicate candidates.
+fn partial_hash(path: &Path) -> io::Result<u128> {
+	let mut file = fs::File::open(path)?;
+	let mut buffer = [0u8; 4096];
+	let read = file.read(&mut buffer)?;
+
+	let mut hasher = SipHasher13::new();
+	hasher.write(&buffer[..read]);
+
+	Ok(hasher.finish128().as_u128())
+}
+
+/// Hashes the entire contents of the specified file, for confirming that partial-hash candidates are truly identical.
+fn full_hash(path: &Path) -> io::Result<u128> {
+	let mut file = fs::File::open(path)?;
+	let mut hasher = SipHasher13::new();
+	let mut buffer = [0u8; 8192];
+
+	loop {
+		let read = file.read(&mut buffer)?;
+
+		if read == 0 {
+			break;
+		}
+
+		hasher.write(&buffer[..read]);
+	}
+
+	Ok(hasher.finish128().as_u128())
 }
 
 impl Error for ProfileError {}
@@ -148,6 +392,123 @@ impl Display for EntryError {
 	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
 		match self {
 			Self::FailedToParse(e) => write!(f, "failed to parse glob pattern [{}]", e),
+			Self::FailedToCompileRegex(e) => write!(f, "failed to compile regex pattern [{}]", e),
+			Self::FailedToResolveRoot(e) => write!(f, "failed to resolve search root [{}]", e),
 		}
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn parse_duration_parses_each_supported_suffix() {
+		assert_eq!(parse_duration("30s").unwrap(), Duration::from_secs(30));
+		assert_eq!(parse_duration("5m").unwrap(), Duration::from_secs(5 * 60));
+		assert_eq!(parse_duration("2h").unwrap(), Duration::from_secs(2 * 60 * 60));
+		assert_eq!(parse_duration("7d").unwrap(), Duration::from_secs(7 * 60 * 60 * 24));
+	}
+
+	#[test]
+	fn parse_duration_rejects_an_unknown_suffix() {
+		assert!(parse_duration("10x").is_err());
+	}
+
+	#[test]
+	fn parse_duration_rejects_a_non_numeric_amount() {
+		assert!(parse_duration("abcd").is_err());
+	}
+
+	#[test]
+	fn parse_duration_does_not_panic_on_a_multi_byte_suffix() {
+		assert!(parse_duration("5日").is_err());
+	}
+
+	/// Creates a fresh, uniquely-named temporary directory for a test to use.
+	fn temp_dir(label: &str) -> PathBuf {
+		use std::sync::atomic::{AtomicUsize, Ordering};
+
+		static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+		let id = COUNTER.fetch_add(1, Ordering::Relaxed);
+		let dir = env::temp_dir().join(format!("item-cleaner-test-{}-{}-{}", std::process::id(), label, id));
+
+		fs::create_dir_all(&dir).unwrap();
+
+		dir
+	}
+
+	#[test]
+	fn dedupe_keeps_one_representative_per_duplicate_set() {
+		let dir = temp_dir("dedupe-identical");
+
+		let a = dir.join("a.txt");
+		let b = dir.join("b.txt");
+		let c = dir.join("c.txt");
+
+		fs::write(&a, b"hello").unwrap();
+		fs::write(&b, b"hello").unwrap();
+		fs::write(&c, b"world").unwrap();
+
+		let duplicates = dedupe(vec![a, b.clone(), c], &Order::FileName);
+
+		assert_eq!(duplicates, vec![b]);
+
+		fs::remove_dir_all(&dir).unwrap();
+	}
+
+	#[test]
+	fn dedupe_does_not_flag_files_of_different_size() {
+		let dir = temp_dir("dedupe-different-size");
+
+		let a = dir.join("a.txt");
+		let b = dir.join("b.txt");
+
+		fs::write(&a, b"hello").unwrap();
+		fs::write(&b, b"hello!").unwrap();
+
+		let duplicates = dedupe(vec![a, b], &Order::FileName);
+
+		assert!(duplicates.is_empty());
+
+		fs::remove_dir_all(&dir).unwrap();
+	}
+
+	#[test]
+	fn dedupe_does_not_flag_a_same_size_partial_hash_collision_with_different_contents() {
+		let dir = temp_dir("dedupe-same-size-different-contents");
+
+		let a = dir.join("a.txt");
+		let b = dir.join("b.txt");
+
+		// Share the whole first 4096-byte block so the partial hashes collide, and differ only after it.
+		let prefix = vec![b'x'; 4096];
+
+		fs::write(&a, [prefix.as_slice(), &b"a"[..]].concat()).unwrap();
+		fs::write(&b, [prefix.as_slice(), &b"b"[..]].concat()).unwrap();
+
+		let duplicates = dedupe(vec![a, b], &Order::FileName);
+
+		assert!(duplicates.is_empty());
+
+		fs::remove_dir_all(&dir).unwrap();
+	}
+
+	#[test]
+	#[cfg(unix)]
+	fn walk_does_not_recurse_into_a_self_referential_symlink() {
+		let dir = temp_dir("walk-symlink-cycle");
+		let link = dir.join("loop");
+
+		std::os::unix::fs::symlink(&dir, &link).unwrap();
+
+		let paths = walk(&dir);
+
+		assert!(paths.contains(&link));
+		assert!(!paths.iter().any(|p| p.starts_with(link.join("loop"))));
+
+		fs::remove_file(&link).unwrap();
+		fs::remove_dir_all(&dir).unwrap();
+	}
+}
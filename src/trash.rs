@@ -0,0 +1,262 @@
+use std::{
+	env,
+	error::Error,
+	ffi::OsStr,
+	fmt::{self, Display},
+	fs,
+	io::{self, Write},
+	path::{Path, PathBuf},
+	time::SystemTime,
+};
+
+use chrono::{DateTime, Local};
+
+/// Represents a trash-related error.
+#[derive(Debug)]
+pub enum TrashError {
+	/// Indicates that the trash home directory could not be resolved.
+	FailedToResolveHome,
+
+	/// Indicates that the trash directory structure could not be created.
+	FailedToCreateDirectory(io::Error),
+
+	/// Indicates that the metadata for a particular path could not be read.
+	FailedToInspectPath(io::Error),
+
+	/// Indicates that a free trash destination could not be reserved.
+	FailedToReserveDestination(io::Error),
+
+	/// Indicates that a particular path could not be moved into the trash.
+	FailedToMove(io::Error),
+
+	/// Indicates that the trash info file could not be written.
+	FailedToWriteInfo(io::Error),
+
+	/// Indicates that a path lacked a file name and so could not be trashed.
+	MissingFileName,
+}
+
+/// Indicates the result of a trash operation, carrying the number of bytes relocated.
+pub type TrashResult = Result<u64, TrashError>;
+
+/// Moves the specified path into the XDG trash, returning the number of bytes relocated.
+pub fn trash<T>(path: T) -> TrashResult
+where
+	T: AsRef<Path>,
+{
+	let path = path.as_ref();
+	let size = size_of(path).map_err(TrashError::FailedToInspectPath)?;
+
+	let home = home().ok_or(TrashError::FailedToResolveHome)?;
+	let files = home.join("files");
+	let info = home.join("info");
+
+	fs::create_dir_all(&files).map_err(TrashError::FailedToCreateDirectory)?;
+	fs::create_dir_all(&info).map_err(TrashError::FailedToCreateDirectory)?;
+
+	let name = path.file_name().ok_or(TrashError::MissingFileName)?;
+	let (destination, mut info_file) = reserve_destination(&files, &info, name).map_err(TrashError::FailedToReserveDestination)?;
+
+	move_path(path, &destination).map_err(TrashError::FailedToMove)?;
+	info_file.write_all(format_info(path).as_bytes()).map_err(TrashError::FailedToWriteInfo)?;
+
+	Ok(size)
+}
+
+/// Resolves the root of the XDG trash, per `$XDG_DATA_HOME/Trash` falling back to `~/.local/share/Trash`.
+fn home() -> Option<PathBuf> {
+	if let Ok(data_home) = env::var("XDG_DATA_HOME") {
+		if !data_home.is_empty() {
+			return Some(PathBuf::from(data_home).join("Trash"));
+		}
+	}
+
+	env::var("HOME").ok().map(|home| PathBuf::from(home).join(".local/share/Trash"))
+}
+
+/// Atomically reserves a free `files/`/`info/` destination pair for the specified name, appending an incrementing
+/// suffix on collision. Reservation is done by exclusively creating the `.trashinfo` file (`create_new`), so two
+/// concurrent callers can never be handed the same destination, closing the TOCTOU window a plain existence check
+/// would leave open.
+fn reserve_destination(files: &Path, info: &Path, name: &OsStr) -> io::Result<(PathBuf, fs::File)> {
+	let mut candidate = name.to_string_lossy().into_owned();
+	let mut suffix = 0u32;
+
+	loop {
+		let destination = files.join(&candidate);
+		let info_path = info.join(format!("{}.trashinfo", candidate));
+
+		match fs::OpenOptions::new().write(true).create_new(true).open(&info_path) {
+			Ok(info_file) if destination.exists() => {
+				// The files/ destination is already taken (e.g. left over from outside this tool), even though we
+				// won the reservation for its info file. Release it and try the next candidate.
+
+				drop(info_file);
+				let _ = fs::remove_file(&info_path);
+			}
+			Ok(info_file) => return Ok((destination, info_file)),
+			Err(e) if e.kind() == io::ErrorKind::AlreadyExists => {}
+			Err(e) => return Err(e),
+		}
+
+		suffix += 1;
+		candidate = format!("{}.{}", name.to_string_lossy(), suffix);
+	}
+}
+
+/// Moves a path to its destination, preferring a same-filesystem rename and falling back to copy-then-delete.
+fn move_path(from: &Path, to: &Path) -> io::Result<()> {
+	if fs::rename(from, to).is_ok() {
+		return Ok(());
+	}
+
+	copy_recursive(from, to)?;
+	remove_recursive(from)
+}
+
+/// Recursively copies a file or directory tree.
+fn copy_recursive(from: &Path, to: &Path) -> io::Result<()> {
+	let metadata = from.metadata()?;
+
+	if metadata.is_dir() {
+		fs::create_dir_all(to)?;
+
+		for entry in fs::read_dir(from)?.flatten() {
+			copy_recursive(&entry.path(), &to.join(entry.file_name()))?;
+		}
+	} else {
+		fs::copy(from, to)?;
+	}
+
+	Ok(())
+}
+
+/// Recursively removes a file or directory tree.
+fn remove_recursive(path: &Path) -> io::Result<()> {
+	let metadata = path.metadata()?;
+
+	if metadata.is_dir() {
+		fs::remove_dir_all(path)
+	} else {
+		fs::remove_file(path)
+	}
+}
+
+/// Computes the total size in bytes of a file or directory tree.
+pub(crate) fn size_of(path: &Path) -> io::Result<u64> {
+	let metadata = path.metadata()?;
+
+	if metadata.is_dir() {
+		let mut size = 0u64;
+
+		for entry in fs::read_dir(path)?.flatten() {
+			size += size_of(&entry.path())?;
+		}
+
+		Ok(size)
+	} else {
+		Ok(metadata.len())
+	}
+}
+
+/// Formats the contents of a `.trashinfo` file for the specified original path.
+fn format_info(path: &Path) -> String {
+	let now: DateTime<Local> = SystemTime::now().into();
+
+	format!("[Trash Info]\nPath={}\nDeletionDate={}\n", percent_encode(&path.display().to_string()), now.format("%Y-%m-%dT%H:%M:%S"))
+}
+
+/// Percent-encodes a path, preserving path separators.
+fn percent_encode(value: &str) -> String {
+	let mut encoded = String::with_capacity(value.len());
+
+	for byte in value.bytes() {
+		match byte {
+			b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' | b'/' => encoded.push(byte as char),
+			_ => encoded.push_str(&format!("%{:02X}", byte)),
+		}
+	}
+
+	encoded
+}
+
+impl Error for TrashError {}
+
+impl Display for TrashError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Self::FailedToResolveHome => write!(f, "failed to resolve trash home directory"),
+			Self::FailedToCreateDirectory(e) => write!(f, "failed to create trash directory [{}]", e),
+			Self::FailedToInspectPath(e) => write!(f, "failed to inspect path [{}]", e),
+			Self::FailedToReserveDestination(e) => write!(f, "failed to reserve trash destination [{}]", e),
+			Self::FailedToMove(e) => write!(f, "failed to move path into trash [{}]", e),
+			Self::FailedToWriteInfo(e) => write!(f, "failed to write trash info [{}]", e),
+			Self::MissingFileName => write!(f, "path has no file name"),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::{
+		sync::atomic::{AtomicUsize, Ordering},
+		thread,
+	};
+
+	use super::*;
+
+	/// Creates a fresh, uniquely-named `files/`/`info/` pair for a test to use.
+	fn temp_trash(label: &str) -> (PathBuf, PathBuf) {
+		static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+		let id = COUNTER.fetch_add(1, Ordering::Relaxed);
+		let root = env::temp_dir().join(format!("item-cleaner-test-{}-{}-{}", std::process::id(), label, id));
+
+		let files = root.join("files");
+		let info = root.join("info");
+
+		fs::create_dir_all(&files).unwrap();
+		fs::create_dir_all(&info).unwrap();
+
+		(files, info)
+	}
+
+	#[test]
+	fn reserve_destination_appends_an_incrementing_suffix_on_collision() {
+		let (files, info) = temp_trash("reserve-collision");
+		let name = OsStr::new("cache.tmp");
+
+		let (first, _) = reserve_destination(&files, &info, name).unwrap();
+		let (second, _) = reserve_destination(&files, &info, name).unwrap();
+
+		assert_eq!(first, files.join("cache.tmp"));
+		assert_eq!(second, files.join("cache.tmp.1"));
+
+		fs::remove_dir_all(files.parent().unwrap()).unwrap();
+	}
+
+	#[test]
+	fn reserve_destination_never_hands_out_the_same_name_to_concurrent_callers() {
+		let (files, info) = temp_trash("reserve-concurrent");
+		let name = "cache.tmp";
+
+		let handles: Vec<_> = (0..8)
+			.map(|_| {
+				let files = files.clone();
+				let info = info.clone();
+
+				thread::spawn(move || reserve_destination(&files, &info, OsStr::new(name)).unwrap().0)
+			})
+			.collect();
+
+		let mut destinations: Vec<PathBuf> = handles.into_iter().map(|handle| handle.join().unwrap()).collect();
+		let total = destinations.len();
+
+		destinations.sort();
+		destinations.dedup();
+
+		assert_eq!(destinations.len(), total);
+
+		fs::remove_dir_all(files.parent().unwrap()).unwrap();
+	}
+}